@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+pub mod service;
+
+/// One entry of a lookup table used by [`DataFormat::Table`].
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TableEntry {
+    pub start: f32,
+    pub end: f32,
+    pub name: String,
+}
+
+/// One piecewise interval of a `ScaleLinear` format: `raw*multiplier+offset` within `[lower, upper]`.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScaleLinearInterval {
+    pub lower: f64,
+    pub upper: f64,
+    pub multiplier: f64,
+    pub offset: f64,
+}
+
+/// Numerator/denominator coefficients of a rational function, ordered `[a0, a1, ..., an]`.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RatFuncCoefficients {
+    pub a: Vec<f64>,
+    pub b: Vec<f64>,
+}
+
+/// One piecewise interval of a [`DataFormat::ScaleRatFunc`] format.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScaleRatFuncInterval {
+    pub lower: f64,
+    pub upper: f64,
+    pub coefficients: RatFuncCoefficients,
+}
+
+/// One `(raw, phys)` breakpoint of a `TableInterpretation` format, sorted by increasing `raw`.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TableInterpPoint {
+    pub raw: f64,
+    pub phys: f64,
+}
+
+/// How a `Service`'s raw response payload is compressed before its `output_params` can be decoded.
+#[serde_as]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum Compression {
+    Zlib,
+}
+
+/// The charset a [`DataFormat::String`] field's bytes are encoded in.
+#[serde_as]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum StringEncoding {
+    Ascii,
+    Latin1,
+    Utf8,
+    Utf16Big,
+    Utf16Little,
+}
+
+/// Describes how the raw bits of a [`service::Parameter`] should be interpreted.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum DataFormat {
+    /// Dump the raw bytes as hex, no further interpretation.
+    HexDump,
+    /// A text field, encoded as `StringEncoding`.
+    String(StringEncoding),
+    Bool {
+        pos_name: Option<String>,
+        neg_name: Option<String>,
+    },
+    Table(Vec<TableEntry>),
+    Identical,
+    Linear {
+        multiplier: f32,
+        offset: f32,
+    },
+    ScaleLinear(Vec<ScaleLinearInterval>),
+    RatFunc(RatFuncCoefficients),
+    ScaleRatFunc(Vec<ScaleRatFuncInterval>),
+    TableInterpretation(Vec<TableInterpPoint>),
+    CompuCode(String),
+}
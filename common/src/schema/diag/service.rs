@@ -1,8 +1,9 @@
-use std::{cmp::min, collections::VecDeque, string::FromUtf8Error};
+use std::{cmp::min, collections::VecDeque, io::Read, string::FromUtf8Error};
 use bit_field::BitArray;
 use byteorder::{BigEndian, ByteOrder, LittleEndian};
+use flate2::read::ZlibDecoder;
 use serde::{Serialize, Deserialize};
-use super::DataFormat;
+use super::{Compression, DataFormat, RatFuncCoefficients, StringEncoding, TableInterpPoint};
 use serde_with::{serde_as};
 
 #[serde_as]
@@ -17,7 +18,10 @@ pub struct Service {
     pub input_params: Vec<Parameter>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     #[serde(default = "Vec::new")]
-    pub output_params: Vec<Parameter>
+    pub output_params: Vec<Parameter>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default = "Option::default")]
+    pub compression: Option<Compression>
 }
 
 impl Service {
@@ -28,6 +32,48 @@ impl Service {
     pub fn service_has_output(&self) -> bool {
         !self.output_params.is_empty()
     }
+
+    /// Decompresses `input` according to `compression`, if set, otherwise returns it unchanged.
+    fn decompress(&self, input: &[u8]) -> std::result::Result<Vec<u8>, ParamDecodeError> {
+        match &self.compression {
+            None => Ok(input.to_vec()),
+            Some(Compression::Zlib) => {
+                let mut out = Vec::new();
+                ZlibDecoder::new(input).read_to_end(&mut out)
+                    .map_err(|_| ParamDecodeError::DecompressionFailed)?;
+                Ok(out)
+            }
+        }
+    }
+
+    /// Decodes every entry of `output_params` from `input`, decompressing first if needed.
+    pub fn decode_outputs(&self, input: &[u8]) -> std::result::Result<Vec<(String, String)>, ParamDecodeError> {
+        let plain = self.decompress(input)?;
+        self.output_params.iter()
+            .map(|p| Ok((p.name.clone(), p.decode_value_to_string(&plain)?)))
+            .collect()
+    }
+
+    /// Like `decode_outputs`, but as numbers (for plotting); skips params that can't plot.
+    pub fn decode_outputs_numeric(&self, input: &[u8]) -> std::result::Result<Vec<(String, f64)>, ParamDecodeError> {
+        let plain = self.decompress(input)?;
+        self.output_params.iter()
+            .filter(|p| p.can_plot())
+            .map(|p| Ok((p.name.clone(), p.decode_value_to_number(&plain)?)))
+            .collect()
+    }
+
+    /// Builds the outgoing request payload by writing `inputs` into a copy of `payload`.
+    pub fn build_request(&self, inputs: &[(&str, ParamValue)]) -> std::result::Result<Vec<u8>, ParamEncodeError> {
+        let mut buf = self.payload.clone();
+        for (name, value) in inputs {
+            let param = self.input_params.iter()
+                .find(|p| &p.name == name)
+                .ok_or_else(|| ParamEncodeError::UnknownValue(name.to_string()))?;
+            param.encode_value(value, &mut buf)?;
+        }
+        Ok(buf)
+    }
 }
 
 #[serde_as]
@@ -42,7 +88,74 @@ pub enum ParamDecodeError {
     NotImplemented,
     BitRangeError,
     DecodeNotSupported,
-    StringDecodeFailure(FromUtf8Error)
+    StringDecodeFailure(FromUtf8Error),
+    Utf16DecodeFailure,
+    DecompressionFailed
+}
+
+/// The storage type a `Parameter`'s raw bits are read into before any `DataFormat` is applied.
+#[serde_as]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum NumericType {
+    U8, U16, U32, U64,
+    I8, I16, I32, I64,
+    F32, F64
+}
+
+impl Default for NumericType {
+    fn default() -> Self {
+        NumericType::U32
+    }
+}
+
+impl NumericType {
+    fn storage_bytes(&self) -> usize {
+        match self {
+            NumericType::U8 | NumericType::I8 => 1,
+            NumericType::U16 | NumericType::I16 => 2,
+            NumericType::U32 | NumericType::I32 | NumericType::F32 => 4,
+            NumericType::U64 | NumericType::I64 | NumericType::F64 => 8
+        }
+    }
+
+    fn is_float(&self) -> bool {
+        matches!(self, NumericType::F32 | NumericType::F64)
+    }
+}
+
+/// A numeric value wide enough to hold any `NumericType` without loss.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scalar {
+    Int(i128),
+    Float(f64)
+}
+
+impl Scalar {
+    pub fn as_f64(&self) -> f64 {
+        match self {
+            Scalar::Int(i) => *i as f64,
+            Scalar::Float(f) => *f
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ParamEncodeError {
+    /// This `DataFormat` has no implemented inverse yet.
+    NotImplemented,
+    /// `start_bit`/`length_bits` doesn't fit inside the destination buffer, or is too wide to encode.
+    BitRangeError,
+    /// This `DataFormat` has no meaningful inverse (e.g. `HexDump`).
+    EncodeNotSupported,
+    /// The caller supplied a value that doesn't match any known name (`Bool`/`Table`) or isn't a parameter on this service.
+    UnknownValue(String)
+}
+
+/// A value supplied by the caller for one of a `Service`'s `input_params`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamValue {
+    Number(f64),
+    Text(String)
 }
 
 #[serde_as]
@@ -61,6 +174,8 @@ pub struct Parameter {
     pub length_bits: usize,
     pub byte_order: ParamByteOrder,
     pub data_format: DataFormat,
+    #[serde(default)]
+    pub numeric_type: NumericType,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default = "Option::default")]
     pub valid_bounds: Option<Limit>,
@@ -75,19 +190,15 @@ impl Parameter {
                 let end_byte = (self.start_bit+self.length_bits)/8;
                 return Ok(format!("{:02X?}", &input[start_byte..min(end_byte, input.len())]))
             }
-            DataFormat::String(_s) => { // TODO take into account encoding of string
-                let start_byte = self.start_bit/8;
-                let end_byte = (self.start_bit+self.length_bits)/8;
-                return Ok(String::from_utf8_lossy(&input[start_byte..end_byte]).to_string())
-            }
+            DataFormat::String(encoding) => return self.decode_string(input, *encoding),
             DataFormat::Bool { pos_name, neg_name } => {
-                return match self.get_number(input)? {
-                    0 => Ok(neg_name.clone().unwrap_or("False".into())),
+                return match self.get_scalar(input)?.as_f64() {
+                    v if v == 0.0 => Ok(neg_name.clone().unwrap_or("False".into())),
                     _ => Ok(pos_name.clone().unwrap_or("True".into()))
                 }
             }
             DataFormat::Table(t) => {
-                let raw = self.get_number(input)? as f32;
+                let raw = self.get_scalar(input)?.as_f64() as f32;
                 for v in t {
                     if v.start>= raw && v.end <= raw {
                         return Ok(v.name.clone());
@@ -96,15 +207,14 @@ impl Parameter {
                 // Our value wasn't found, undefined value?
                 return Ok(format!("Undefined ({})", raw));
             }
-            DataFormat::Identical => result.push_str(format!("{}", self.get_number(input)? as f32).as_str()),
+            DataFormat::Identical => result.push_str(format!("{}", self.get_scalar(input)?.as_f64()).as_str()),
             DataFormat::Linear { multiplier, offset } => {
-                let res = self.get_number(input)? as f32;
-                result.push_str(format!("{}", (res*multiplier) + offset).as_str())
+                let res = self.get_scalar(input)?.as_f64();
+                result.push_str(format!("{}", (res * *multiplier as f64) + *offset as f64).as_str())
             },
-            DataFormat::ScaleLinear => return Err(ParamDecodeError::NotImplemented),
-            DataFormat::RatFunc => return Err(ParamDecodeError::NotImplemented),
-            DataFormat::ScaleRatFunc => return Err(ParamDecodeError::NotImplemented),
-            DataFormat::TableInterpretation => return Err(ParamDecodeError::NotImplemented),
+            DataFormat::ScaleLinear(_) | DataFormat::RatFunc(_) | DataFormat::ScaleRatFunc(_) |
+                DataFormat::TableInterpretation(_) =>
+                result.push_str(format!("{}", self.decode_value_to_number(input)?).as_str()),
             DataFormat::CompuCode(_) => return Err(ParamDecodeError::NotImplemented)
         }
         // For numbers
@@ -115,22 +225,103 @@ impl Parameter {
         Ok(result)
     }
 
-    pub fn decode_value_to_number(&self, input: &[u8]) -> std::result::Result<f32, ParamDecodeError> {
+    pub fn decode_value_to_number(&self, input: &[u8]) -> std::result::Result<f64, ParamDecodeError> {
         match &self.data_format {
             DataFormat::HexDump => Err(ParamDecodeError::DecodeNotSupported),
             DataFormat::String(_) => Err(ParamDecodeError::DecodeNotSupported),
-            DataFormat::Bool { pos_name: _, neg_name: _ } => Ok(self.get_number(input)? as f32),
+            DataFormat::Bool { pos_name: _, neg_name: _ } => Ok(self.get_scalar(input)?.as_f64()),
             DataFormat::Table(_) => Err(ParamDecodeError::DecodeNotSupported),
             DataFormat::Identical => Err(ParamDecodeError::NotImplemented),
-            DataFormat::Linear { multiplier, offset } => Ok((self.get_number(input)? as f32 * multiplier) + offset),
-            DataFormat::ScaleLinear => Err(ParamDecodeError::NotImplemented),
-            DataFormat::RatFunc => Err(ParamDecodeError::NotImplemented),
-            DataFormat::ScaleRatFunc => Err(ParamDecodeError::NotImplemented),
-            DataFormat::TableInterpretation => Err(ParamDecodeError::NotImplemented),
+            DataFormat::Linear { multiplier, offset } =>
+                Ok((self.get_scalar(input)?.as_f64() * *multiplier as f64) + *offset as f64),
+            DataFormat::ScaleLinear(intervals) => {
+                let raw = self.get_scalar(input)?.as_f64();
+                let interval = intervals.iter()
+                    .find(|i| raw >= i.lower && raw <= i.upper)
+                    .ok_or(ParamDecodeError::DecodeNotSupported)?;
+                Ok(raw * interval.multiplier + interval.offset)
+            },
+            DataFormat::RatFunc(coeffs) => {
+                let raw = self.get_scalar(input)?.as_f64();
+                Self::eval_rat_func(coeffs, raw)
+            },
+            DataFormat::ScaleRatFunc(intervals) => {
+                let raw = self.get_scalar(input)?.as_f64();
+                let interval = intervals.iter()
+                    .find(|i| raw >= i.lower && raw <= i.upper)
+                    .ok_or(ParamDecodeError::DecodeNotSupported)?;
+                Self::eval_rat_func(&interval.coefficients, raw)
+            },
+            DataFormat::TableInterpretation(points) => {
+                let raw = self.get_scalar(input)?.as_f64();
+                Self::interpolate_table(points, raw)
+            },
             DataFormat::CompuCode(_) => Err(ParamDecodeError::NotImplemented)
         }
     }
 
+    /// Decodes the parameter's bit range as a string in the given encoding.
+    fn decode_string(&self, input: &[u8], encoding: StringEncoding) -> std::result::Result<String, ParamDecodeError> {
+        let start_byte = self.start_bit / 8;
+        let end_byte = min((self.start_bit + self.length_bits) / 8, input.len());
+        if start_byte > end_byte {
+            return Err(ParamDecodeError::BitRangeError)
+        }
+        let slice = &input[start_byte..end_byte];
+        let decoded = match encoding {
+            StringEncoding::Ascii => slice.iter()
+                .map(|&b| if b < 0x80 { b as char } else { std::char::REPLACEMENT_CHARACTER })
+                .collect(),
+            // Latin-1 (ISO 8859-1) maps byte N directly to code point U+00NN.
+            StringEncoding::Latin1 => slice.iter().map(|&b| b as char).collect(),
+            StringEncoding::Utf8 => String::from_utf8(slice.to_vec())
+                .map_err(ParamDecodeError::StringDecodeFailure)?,
+            StringEncoding::Utf16Big | StringEncoding::Utf16Little => {
+                let units: Vec<u16> = slice.chunks_exact(2)
+                    .map(|pair| match encoding {
+                        StringEncoding::Utf16Big => BigEndian::read_u16(pair),
+                        _ => LittleEndian::read_u16(pair)
+                    })
+                    .collect();
+                String::from_utf16(&units).map_err(|_| ParamDecodeError::Utf16DecodeFailure)?
+            }
+        };
+        Ok(decoded.split('\u{0}').next().unwrap_or("").to_string())
+    }
+
+    /// Evaluates `(Σ aᵢ·xⁱ) / (Σ bⱼ·xʲ)` via Horner's method.
+    fn eval_rat_func(coeffs: &RatFuncCoefficients, x: f64) -> std::result::Result<f64, ParamDecodeError> {
+        let numerator = Self::eval_poly(&coeffs.a, x);
+        let denominator = Self::eval_poly(&coeffs.b, x);
+        if denominator.abs() < f64::EPSILON {
+            return Err(ParamDecodeError::DecodeNotSupported)
+        }
+        Ok(numerator / denominator)
+    }
+
+    /// Evaluates `Σ coeffs[i]·x^i` via Horner's method.
+    fn eval_poly(coeffs: &[f64], x: f64) -> f64 {
+        coeffs.iter().rev().fold(0.0, |acc, c| acc * x + c)
+    }
+
+    /// Linearly interpolates between the breakpoints bracketing `raw`, clamping outside the ends.
+    fn interpolate_table(points: &[TableInterpPoint], raw: f64) -> std::result::Result<f64, ParamDecodeError> {
+        if points.is_empty() {
+            return Err(ParamDecodeError::DecodeNotSupported)
+        }
+        if raw <= points[0].raw {
+            return Ok(points[0].phys)
+        }
+        if raw >= points[points.len() - 1].raw {
+            return Ok(points[points.len() - 1].phys)
+        }
+        let upper_idx = points.iter().position(|p| p.raw >= raw).unwrap();
+        let lo = &points[upper_idx - 1];
+        let hi = &points[upper_idx];
+        let t = (raw - lo.raw) / (hi.raw - lo.raw);
+        Ok(lo.phys + t * (hi.phys - lo.phys))
+    }
+
     /// Returns if the data type is capable of being plotted on a chart or not
     pub fn can_plot(&self) -> bool {
         match &self.data_format {
@@ -140,15 +331,131 @@ impl Parameter {
             DataFormat::Table(_) => false,
             DataFormat::Identical => true,
             DataFormat::Linear { multiplier: _, offset: _ } => true,
-            DataFormat::ScaleLinear => false,
-            DataFormat::RatFunc => false,
-            DataFormat::ScaleRatFunc => false,
-            DataFormat::TableInterpretation => false,
+            DataFormat::ScaleLinear(_) => true,
+            DataFormat::RatFunc(_) => true,
+            DataFormat::ScaleRatFunc(_) => true,
+            DataFormat::TableInterpretation(_) => true,
             DataFormat::CompuCode(_) => false
         }
     }
 
 
+    /// Writes `value` into `buf` at this parameter's bit range, applying the inverse of
+    /// this parameter's `DataFormat`.
+    pub fn encode_value(&self, value: &ParamValue, buf: &mut Vec<u8>) -> std::result::Result<(), ParamEncodeError> {
+        match (&self.data_format, value) {
+            (DataFormat::String(_), ParamValue::Text(s)) => self.encode_string(s, buf),
+            (DataFormat::Bool { pos_name, neg_name }, ParamValue::Text(s)) => {
+                let pos = pos_name.as_deref().unwrap_or("True");
+                let neg = neg_name.as_deref().unwrap_or("False");
+                let raw = if s == pos {
+                    1.0
+                } else if s == neg {
+                    0.0
+                } else {
+                    return Err(ParamEncodeError::UnknownValue(s.clone()))
+                };
+                self.encode_value_from_number(raw, buf)
+            }
+            (DataFormat::Table(t), ParamValue::Text(s)) => {
+                let entry = t.iter().find(|e| &e.name == s)
+                    .ok_or_else(|| ParamEncodeError::UnknownValue(s.clone()))?;
+                self.write_scalar(entry.start as f64, buf)
+            }
+            (_, ParamValue::Number(n)) => self.encode_value_from_number(*n, buf),
+            _ => Err(ParamEncodeError::EncodeNotSupported)
+        }
+    }
+
+    /// Packs a raw number into this parameter's bit range, inverting `DataFormat::Linear`
+    /// (`internal = (value - offset) / multiplier`, rounded) first.
+    pub fn encode_value_from_number(&self, value: f64, buf: &mut Vec<u8>) -> std::result::Result<(), ParamEncodeError> {
+        let raw = match &self.data_format {
+            DataFormat::Linear { multiplier, offset } => {
+                if *multiplier == 0.0 {
+                    return Err(ParamEncodeError::EncodeNotSupported)
+                }
+                (value - *offset as f64) / *multiplier as f64
+            },
+            DataFormat::Identical | DataFormat::Bool { pos_name: _, neg_name: _ } => value,
+            DataFormat::HexDump | DataFormat::String(_) | DataFormat::Table(_) =>
+                return Err(ParamEncodeError::EncodeNotSupported),
+            DataFormat::ScaleLinear(_) | DataFormat::RatFunc(_) | DataFormat::ScaleRatFunc(_) |
+                DataFormat::TableInterpretation(_) | DataFormat::CompuCode(_) =>
+                return Err(ParamEncodeError::NotImplemented)
+        };
+        self.write_scalar(raw, buf)
+    }
+
+    fn encode_string(&self, s: &str, buf: &mut Vec<u8>) -> std::result::Result<(), ParamEncodeError> {
+        let start_byte = self.start_bit / 8;
+        let end_byte = (self.start_bit + self.length_bits) / 8;
+        if end_byte > buf.len() {
+            buf.resize(end_byte, 0);
+        }
+        let bytes = s.as_bytes();
+        for i in 0..(end_byte - start_byte) {
+            buf[start_byte + i] = *bytes.get(i).unwrap_or(&0);
+        }
+        Ok(())
+    }
+
+    /// Packs `value` into this parameter's `numeric_type` storage word and writes the bytes
+    /// covering `length_bits` back into `buf`, growing it if needed.
+    fn write_scalar(&self, value: f64, buf: &mut Vec<u8>) -> std::result::Result<(), ParamEncodeError> {
+        let width = self.numeric_type.storage_bytes();
+        if self.length_bits == 0 || self.length_bits > width * 8 {
+            return Err(ParamEncodeError::BitRangeError)
+        }
+        let mut word = vec![0u8; width];
+        if self.numeric_type.is_float() {
+            match (self.numeric_type, self.byte_order) {
+                (NumericType::F32, ParamByteOrder::BigEndian) => BigEndian::write_f32(&mut word, value as f32),
+                (NumericType::F32, ParamByteOrder::LittleEndian) => LittleEndian::write_f32(&mut word, value as f32),
+                (NumericType::F64, ParamByteOrder::BigEndian) => BigEndian::write_f64(&mut word, value),
+                (NumericType::F64, ParamByteOrder::LittleEndian) => LittleEndian::write_f64(&mut word, value),
+                _ => unreachable!("is_float() only matches F32/F64")
+            }
+        } else {
+            let raw = value.round() as i128 as u64;
+            match (width, self.byte_order) {
+                (1, _) => word[0] = raw as u8,
+                (2, ParamByteOrder::BigEndian) => BigEndian::write_u16(&mut word, raw as u16),
+                (2, ParamByteOrder::LittleEndian) => LittleEndian::write_u16(&mut word, raw as u16),
+                (4, ParamByteOrder::BigEndian) => BigEndian::write_u32(&mut word, raw as u32),
+                (4, ParamByteOrder::LittleEndian) => LittleEndian::write_u32(&mut word, raw as u32),
+                (8, ParamByteOrder::BigEndian) => BigEndian::write_u64(&mut word, raw),
+                (8, ParamByteOrder::LittleEndian) => LittleEndian::write_u64(&mut word, raw),
+                _ => unreachable!("storage_bytes() only returns 1, 2, 4 or 8")
+            }
+        }
+
+        let nbytes = (self.length_bits + 7) / 8;
+        let src = match self.byte_order {
+            ParamByteOrder::BigEndian => &word[width - nbytes..],
+            ParamByteOrder::LittleEndian => &word[..nbytes]
+        };
+
+        let end_byte = (self.start_bit + self.length_bits + 7) / 8;
+        if end_byte > buf.len() {
+            buf.resize(end_byte, 0);
+        }
+        self.write_bit_chunks(src, buf);
+        Ok(())
+    }
+
+    /// Writes `bytes` into `buf`'s `start_bit..start_bit+length_bits` slice, one byte at a time.
+    fn write_bit_chunks(&self, bytes: &[u8], buf: &mut Vec<u8>) {
+        let mut start = self.start_bit;
+        let mut i = 0;
+        while start < self.start_bit + self.length_bits {
+            let max_write = min(self.start_bit + self.length_bits, start + 8);
+            buf.set_bits(start..max_write, bytes[i].get_bits(0..max_write - start));
+            start += 8;
+            i += 1;
+        }
+    }
+
     pub fn get_unit(&self) -> Option<String> {
         if self.unit.is_empty() {
             None
@@ -157,46 +464,388 @@ impl Parameter {
         }
     }
 
-    fn get_number(&self, resp: &[u8]) -> std::result::Result<u32, ParamDecodeError> {
-        if self.length_bits <= 32 {
-            let result = std::panic::catch_unwind(||{
-                if self.length_bits <= 8 {
-                    resp.get_bits(self.start_bit..self.start_bit+self.length_bits) as u32
-                } else {
-                    let mut res = 0;
-                    let mut buf: Vec<u8> = Vec::new();
-                    let mut start = self.start_bit;
-                    while start < self.length_bits + self.start_bit {
-                        let max_read = min(self.start_bit + self.length_bits, start + 8);
-                        buf.push(resp.get_bits(start..max_read));
-                        start += 8;
-                    }
-                    
-                    if buf.len() > 4 {
-                        panic!("Number too big!") // Cannot handle more than 32bits atm
-                    } else {
-                        if buf.len() >= 4 {                        
-                            res = match self.byte_order {
-                                ParamByteOrder::BigEndian => BigEndian::read_u32(&buf),
-                                ParamByteOrder::LittleEndian => LittleEndian::read_u32(&buf)
-                            }
-                        } else if buf.len() >= 2 {
-                            res = match self.byte_order {
-                                ParamByteOrder::BigEndian => BigEndian::read_u16(&buf) as u32,
-                                ParamByteOrder::LittleEndian => LittleEndian::read_u16(&buf) as u32
-                            }
-                        }
-                        res as u32
-                    }
-                }
-            });
+    /// Reads this parameter's bit range as `numeric_type`, sign-extending signed ints
+    /// when `length_bits` is narrower than the storage width.
+    fn get_scalar(&self, resp: &[u8]) -> std::result::Result<Scalar, ParamDecodeError> {
+        let width = self.numeric_type.storage_bytes();
+        if self.length_bits == 0 || self.length_bits > width * 8 ||
+            self.start_bit + self.length_bits > resp.len() * 8 {
+            return Err(ParamDecodeError::BitRangeError)
+        }
+        let raw_bytes = self.read_bit_chunks(resp);
+        let mut word = vec![0u8; width];
+        match self.byte_order {
+            // Bytes arrive most-significant-first; right-align them in the storage word.
+            ParamByteOrder::BigEndian => word[width - raw_bytes.len()..].copy_from_slice(&raw_bytes),
+            ParamByteOrder::LittleEndian => word[..raw_bytes.len()].copy_from_slice(&raw_bytes)
+        }
+
+        let read_order = |read_be: fn(&[u8]) -> u64, read_le: fn(&[u8]) -> u64| match self.byte_order {
+            ParamByteOrder::BigEndian => read_be(&word),
+            ParamByteOrder::LittleEndian => read_le(&word)
+        };
+
+        Ok(match self.numeric_type {
+            NumericType::U8 => Scalar::Int(word[0] as i128),
+            NumericType::U16 => Scalar::Int(read_order(
+                |b| BigEndian::read_u16(b) as u64, |b| LittleEndian::read_u16(b) as u64) as i128),
+            NumericType::U32 => Scalar::Int(read_order(
+                |b| BigEndian::read_u32(b) as u64, |b| LittleEndian::read_u32(b) as u64) as i128),
+            NumericType::U64 => Scalar::Int(read_order(
+                BigEndian::read_u64, LittleEndian::read_u64) as i128),
+            NumericType::I8 => Scalar::Int(Self::sign_extend(word[0] as i128, self.length_bits.min(8))),
+            NumericType::I16 => Scalar::Int(Self::sign_extend(read_order(
+                |b| BigEndian::read_u16(b) as u64, |b| LittleEndian::read_u16(b) as u64) as i128, self.length_bits.min(16))),
+            NumericType::I32 => Scalar::Int(Self::sign_extend(read_order(
+                |b| BigEndian::read_u32(b) as u64, |b| LittleEndian::read_u32(b) as u64) as i128, self.length_bits.min(32))),
+            NumericType::I64 => Scalar::Int(Self::sign_extend(read_order(
+                BigEndian::read_u64, LittleEndian::read_u64) as i128, self.length_bits.min(64))),
+            NumericType::F32 => Scalar::Float(match self.byte_order {
+                ParamByteOrder::BigEndian => BigEndian::read_f32(&word),
+                ParamByteOrder::LittleEndian => LittleEndian::read_f32(&word)
+            } as f64),
+            NumericType::F64 => Scalar::Float(match self.byte_order {
+                ParamByteOrder::BigEndian => BigEndian::read_f64(&word),
+                ParamByteOrder::LittleEndian => LittleEndian::read_f64(&word)
+            })
+        })
+    }
+
+    fn sign_extend(value: i128, bits: usize) -> i128 {
+        if bits == 0 || bits >= 128 {
+            return value
+        }
+        let shift = 128 - bits;
+        (value << shift) >> shift
+    }
+
+    /// Collects this parameter's bit range from `resp` into a byte buffer, one byte at a time.
+    fn read_bit_chunks(&self, resp: &[u8]) -> Vec<u8> {
+        let mut raw_bytes = Vec::new();
+        let mut start = self.start_bit;
+        while start < self.start_bit + self.length_bits {
+            let max_read = min(self.start_bit + self.length_bits, start + 8);
+            raw_bytes.push(resp.get_bits(start..max_read));
+            start += 8;
+        }
+        raw_bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-            match result {
-                Ok(r) => Ok(r as u32),
-                Err(_) => Err(ParamDecodeError::BitRangeError)
+    fn param(start_bit: usize, length_bits: usize, byte_order: ParamByteOrder, numeric_type: NumericType) -> Parameter {
+        param_with_format(start_bit, length_bits, byte_order, numeric_type, DataFormat::Identical)
+    }
+
+    fn param_with_format(start_bit: usize, length_bits: usize, byte_order: ParamByteOrder,
+                          numeric_type: NumericType, data_format: DataFormat) -> Parameter {
+        Parameter {
+            name: "p".into(),
+            unit: String::new(),
+            start_bit,
+            length_bits,
+            byte_order,
+            data_format,
+            numeric_type,
+            valid_bounds: None
+        }
+    }
+
+    #[test]
+    fn get_scalar_write_scalar_round_trip_ints() {
+        let cases = [
+            (NumericType::U8, 8), (NumericType::U16, 16), (NumericType::U32, 32), (NumericType::U64, 64),
+            (NumericType::I8, 8), (NumericType::I16, 16), (NumericType::I32, 32), (NumericType::I64, 64)
+        ];
+        for (nt, bits) in cases {
+            for order in [ParamByteOrder::BigEndian, ParamByteOrder::LittleEndian] {
+                let p = param(0, bits, order, nt);
+                let mut buf = vec![0u8; bits / 8];
+                p.write_scalar(42.0, &mut buf).unwrap();
+                assert_eq!(p.get_scalar(&buf).unwrap().as_f64(), 42.0, "{:?}/{:?}", nt, order);
             }
-        } else {
-            Err(ParamDecodeError::BitRangeError)
         }
     }
+
+    #[test]
+    fn get_scalar_write_scalar_round_trip_floats() {
+        for (nt, bits) in [(NumericType::F32, 32), (NumericType::F64, 64)] {
+            for order in [ParamByteOrder::BigEndian, ParamByteOrder::LittleEndian] {
+                let p = param(0, bits, order, nt);
+                let mut buf = vec![0u8; bits / 8];
+                p.write_scalar(3.5, &mut buf).unwrap();
+                assert!((p.get_scalar(&buf).unwrap().as_f64() - 3.5).abs() < 1e-6, "{:?}/{:?}", nt, order);
+            }
+        }
+    }
+
+    #[test]
+    fn get_scalar_sign_extends_narrow_field_inside_wider_storage() {
+        // 4-bit field inside an I8 storage word, starting mid-byte.
+        let p = param(4, 4, ParamByteOrder::BigEndian, NumericType::I8);
+        let mut buf = vec![0u8; 1];
+        p.write_scalar(-1.0, &mut buf).unwrap();
+        assert_eq!(p.get_scalar(&buf).unwrap().as_f64(), -1.0);
+    }
+
+    #[test]
+    fn get_scalar_rejects_length_wider_than_numeric_type_storage() {
+        // Regression test: length_bits wider than numeric_type's storage must error, not panic.
+        let p = param(0, 40, ParamByteOrder::BigEndian, NumericType::U32);
+        let buf = vec![0u8; 8];
+        assert!(matches!(p.get_scalar(&buf), Err(ParamDecodeError::BitRangeError)));
+    }
+
+    #[test]
+    fn write_scalar_rejects_length_wider_than_numeric_type_storage() {
+        let p = param(0, 40, ParamByteOrder::BigEndian, NumericType::U32);
+        let mut buf = vec![0u8; 8];
+        assert!(matches!(p.write_scalar(1.0, &mut buf), Err(ParamEncodeError::BitRangeError)));
+    }
+
+    #[test]
+    fn get_scalar_rejects_range_past_end_of_input() {
+        let p = param(0, 32, ParamByteOrder::BigEndian, NumericType::U32);
+        let buf = vec![0u8; 2];
+        assert!(matches!(p.get_scalar(&buf), Err(ParamDecodeError::BitRangeError)));
+    }
+
+    #[test]
+    fn scale_linear_picks_interval_and_applies_it() {
+        let intervals = vec![
+            ScaleLinearInterval { lower: 0.0, upper: 100.0, multiplier: 2.0, offset: 1.0 }
+        ];
+        let p = param_with_format(0, 8, ParamByteOrder::BigEndian, NumericType::U8,
+            DataFormat::ScaleLinear(intervals));
+        assert_eq!(p.decode_value_to_number(&[10]).unwrap(), 21.0);
+    }
+
+    #[test]
+    fn scale_linear_out_of_range_is_decode_not_supported() {
+        let intervals = vec![
+            ScaleLinearInterval { lower: 0.0, upper: 5.0, multiplier: 2.0, offset: 1.0 }
+        ];
+        let p = param_with_format(0, 8, ParamByteOrder::BigEndian, NumericType::U8,
+            DataFormat::ScaleLinear(intervals));
+        assert!(matches!(p.decode_value_to_number(&[10]), Err(ParamDecodeError::DecodeNotSupported)));
+    }
+
+    #[test]
+    fn rat_func_evaluates_via_horner() {
+        // (1 + x) / 1, x = 5 -> 6
+        let coeffs = RatFuncCoefficients { a: vec![1.0, 1.0], b: vec![1.0] };
+        let p = param_with_format(0, 8, ParamByteOrder::BigEndian, NumericType::U8,
+            DataFormat::RatFunc(coeffs));
+        assert_eq!(p.decode_value_to_number(&[5]).unwrap(), 6.0);
+    }
+
+    #[test]
+    fn rat_func_zero_denominator_is_decode_not_supported() {
+        let coeffs = RatFuncCoefficients { a: vec![1.0], b: vec![0.0] };
+        let p = param_with_format(0, 8, ParamByteOrder::BigEndian, NumericType::U8,
+            DataFormat::RatFunc(coeffs));
+        assert!(matches!(p.decode_value_to_number(&[5]), Err(ParamDecodeError::DecodeNotSupported)));
+    }
+
+    #[test]
+    fn scale_rat_func_picks_interval_and_evaluates() {
+        let intervals = vec![
+            ScaleRatFuncInterval {
+                lower: 0.0, upper: 100.0,
+                coefficients: RatFuncCoefficients { a: vec![0.0, 2.0], b: vec![1.0] }
+            }
+        ];
+        let p = param_with_format(0, 8, ParamByteOrder::BigEndian, NumericType::U8,
+            DataFormat::ScaleRatFunc(intervals));
+        assert_eq!(p.decode_value_to_number(&[5]).unwrap(), 10.0);
+    }
+
+    #[test]
+    fn table_interpretation_interpolates_between_breakpoints() {
+        let points = vec![
+            TableInterpPoint { raw: 0.0, phys: 0.0 },
+            TableInterpPoint { raw: 10.0, phys: 100.0 }
+        ];
+        let p = param_with_format(0, 8, ParamByteOrder::BigEndian, NumericType::U8,
+            DataFormat::TableInterpretation(points));
+        assert_eq!(p.decode_value_to_number(&[5]).unwrap(), 50.0);
+    }
+
+    #[test]
+    fn table_interpretation_clamps_outside_breakpoints() {
+        let points = vec![
+            TableInterpPoint { raw: 0.0, phys: 10.0 },
+            TableInterpPoint { raw: 10.0, phys: 20.0 }
+        ];
+        let p = param_with_format(0, 8, ParamByteOrder::BigEndian, NumericType::U8,
+            DataFormat::TableInterpretation(points));
+        assert_eq!(p.decode_value_to_number(&[0]).unwrap(), 10.0);
+        assert_eq!(p.decode_value_to_number(&[255]).unwrap(), 20.0);
+    }
+
+    fn string_param(start_bit: usize, length_bits: usize, encoding: StringEncoding) -> Parameter {
+        param_with_format(start_bit, length_bits, ParamByteOrder::BigEndian, NumericType::U8,
+            DataFormat::String(encoding))
+    }
+
+    #[test]
+    fn decode_string_ascii_trims_trailing_nul() {
+        let p = string_param(0, 32, StringEncoding::Ascii);
+        assert_eq!(p.decode_value_to_string(b"hi\0\0").unwrap(), "hi");
+    }
+
+    #[test]
+    fn decode_string_ascii_replaces_non_ascii_bytes() {
+        let p = string_param(0, 16, StringEncoding::Ascii);
+        assert_eq!(p.decode_value_to_string(&[b'a', 0xFF]).unwrap(),
+            format!("a{}", std::char::REPLACEMENT_CHARACTER));
+    }
+
+    #[test]
+    fn decode_string_latin1_maps_bytes_directly() {
+        let p = string_param(0, 8, StringEncoding::Latin1);
+        assert_eq!(p.decode_value_to_string(&[0xE9]).unwrap(), "\u{E9}");
+    }
+
+    #[test]
+    fn decode_string_utf8_decodes_valid_bytes() {
+        let p = string_param(0, 40, StringEncoding::Utf8);
+        assert_eq!(p.decode_value_to_string("hi\0\0\0".as_bytes()).unwrap(), "hi");
+    }
+
+    #[test]
+    fn decode_string_utf8_rejects_invalid_bytes() {
+        let p = string_param(0, 16, StringEncoding::Utf8);
+        assert!(matches!(p.decode_value_to_string(&[0xFF, 0xFE]), Err(ParamDecodeError::StringDecodeFailure(_))));
+    }
+
+    #[test]
+    fn decode_string_utf16_big_and_little_round_trip() {
+        // 'A' = U+0041
+        let big = string_param(0, 16, StringEncoding::Utf16Big);
+        assert_eq!(big.decode_value_to_string(&[0x00, 0x41]).unwrap(), "A");
+        let little = string_param(0, 16, StringEncoding::Utf16Little);
+        assert_eq!(little.decode_value_to_string(&[0x41, 0x00]).unwrap(), "A");
+    }
+
+    #[test]
+    fn decode_string_utf16_rejects_lone_surrogate() {
+        let p = string_param(0, 16, StringEncoding::Utf16Big);
+        assert!(matches!(p.decode_value_to_string(&[0xD8, 0x00]), Err(ParamDecodeError::Utf16DecodeFailure)));
+    }
+
+    #[test]
+    fn decode_string_rejects_start_byte_past_end_byte() {
+        // start_bit puts start_byte past the clamped end_byte of a too-short input.
+        let p = string_param(80, 8, StringEncoding::Ascii);
+        assert!(matches!(p.decode_value_to_string(&[0u8; 2]), Err(ParamDecodeError::BitRangeError)));
+    }
+
+    fn zlib_compress(data: &[u8]) -> Vec<u8> {
+        use std::io::Write;
+        let mut enc = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        enc.write_all(data).unwrap();
+        enc.finish().unwrap()
+    }
+
+    fn compressed_service(output_params: Vec<Parameter>) -> Service {
+        Service {
+            name: "s".into(),
+            description: String::new(),
+            payload: Vec::new(),
+            input_params: Vec::new(),
+            output_params,
+            compression: Some(Compression::Zlib)
+        }
+    }
+
+    #[test]
+    fn decode_outputs_decompresses_before_decoding() {
+        let svc = compressed_service(vec![string_param(0, 16, StringEncoding::Ascii)]);
+        let compressed = zlib_compress(b"hi");
+        assert_eq!(svc.decode_outputs(&compressed).unwrap(), vec![("p".to_string(), "hi".to_string())]);
+    }
+
+    #[test]
+    fn decode_outputs_numeric_decompresses_and_skips_unplottable_params() {
+        let string_field = string_param(0, 16, StringEncoding::Ascii);
+        let numeric_field = param_with_format(16, 8, ParamByteOrder::BigEndian, NumericType::U8, DataFormat::Identical);
+        let svc = compressed_service(vec![string_field, numeric_field]);
+        let compressed = zlib_compress(&[b'h', b'i', 42]);
+        assert_eq!(svc.decode_outputs_numeric(&compressed).unwrap(), vec![("p".to_string(), 42.0)]);
+    }
+
+    #[test]
+    fn decode_outputs_reports_decompression_failure_on_garbage_input() {
+        let svc = compressed_service(vec![string_param(0, 16, StringEncoding::Ascii)]);
+        assert!(matches!(svc.decode_outputs(&[0xDE, 0xAD, 0xBE, 0xEF]), Err(ParamDecodeError::DecompressionFailed)));
+    }
+
+    fn input_service(input_params: Vec<Parameter>) -> Service {
+        Service {
+            name: "s".into(),
+            description: String::new(),
+            payload: vec![0u8; 4],
+            input_params,
+            output_params: Vec::new(),
+            compression: None
+        }
+    }
+
+    #[test]
+    fn build_request_encodes_bool_with_default_true_false_names() {
+        let mut p = param_with_format(0, 8, ParamByteOrder::BigEndian, NumericType::U8,
+            DataFormat::Bool { pos_name: None, neg_name: None });
+        p.name = "flag".into();
+        let svc = input_service(vec![p]);
+
+        let req = svc.build_request(&[("flag", ParamValue::Text("True".into()))]).unwrap();
+        assert_eq!(req[0], 1);
+        let req = svc.build_request(&[("flag", ParamValue::Text("False".into()))]).unwrap();
+        assert_eq!(req[0], 0);
+    }
+
+    #[test]
+    fn build_request_encodes_bool_with_custom_names() {
+        let mut p = param_with_format(0, 8, ParamByteOrder::BigEndian, NumericType::U8,
+            DataFormat::Bool { pos_name: Some("On".into()), neg_name: Some("Off".into()) });
+        p.name = "flag".into();
+        let svc = input_service(vec![p]);
+
+        let req = svc.build_request(&[("flag", ParamValue::Text("On".into()))]).unwrap();
+        assert_eq!(req[0], 1);
+    }
+
+    #[test]
+    fn build_request_rejects_bool_value_matching_neither_name() {
+        let mut p = param_with_format(0, 8, ParamByteOrder::BigEndian, NumericType::U8,
+            DataFormat::Bool { pos_name: Some("On".into()), neg_name: Some("Off".into()) });
+        p.name = "flag".into();
+        let svc = input_service(vec![p]);
+
+        assert!(matches!(svc.build_request(&[("flag", ParamValue::Text("True".into()))]),
+            Err(ParamEncodeError::UnknownValue(_))));
+    }
+
+    #[test]
+    fn build_request_inverts_linear_scaling() {
+        let mut p = param_with_format(0, 8, ParamByteOrder::BigEndian, NumericType::U8,
+            DataFormat::Linear { multiplier: 2.0, offset: 1.0 });
+        p.name = "temp".into();
+        let svc = input_service(vec![p]);
+
+        // physical value 21 -> raw (21 - 1) / 2 = 10
+        let req = svc.build_request(&[("temp", ParamValue::Number(21.0))]).unwrap();
+        assert_eq!(req[0], 10);
+    }
+
+    #[test]
+    fn build_request_rejects_unknown_param_name() {
+        let svc = input_service(Vec::new());
+        assert!(matches!(svc.build_request(&[("missing", ParamValue::Number(1.0))]),
+            Err(ParamEncodeError::UnknownValue(_))));
+    }
 }
\ No newline at end of file